@@ -0,0 +1,105 @@
+// A box-selection subsystem for gathering a set of input boxes that
+// cover a given nanoErg/token target.
+//
+// Actions such as `action_bootstrap_math_bounty_box` otherwise require
+// the caller to pre-supply exactly the right `ErgsBox` inputs by hand.
+// `select_boxes` removes that burden by greedily accumulating boxes
+// from an available set until both the nanoErg target and every token
+// target are met, handing back the chosen boxes plus the leftover
+// change for the balancer (see `tx_creation::balance_and_create_unsigned_tx`).
+
+use crate::stage::{BoxVerificationError, ErgoBox, Result};
+use ergo_lib::chain::token::{Token, TokenAmount, TokenId};
+use ergo_offchain_utilities::NanoErg;
+use std::collections::HashMap;
+
+/// Tally up how much of each `TokenId` a box holds.
+fn box_token_amounts(b: &ErgoBox) -> HashMap<TokenId, u64> {
+    b.tokens
+        .iter()
+        .map(|t| (t.token_id.clone(), *t.amount.as_u64()))
+        .collect()
+}
+
+/// Greedily accumulate boxes from `available` until the selected set
+/// holds at least `target_nano_ergs` nanoErgs and at least
+/// `target_tokens` of every requested token, preferring boxes which
+/// already hold a needed `TokenId` first so that fewer inputs are
+/// required overall.
+///
+/// Returns the chosen boxes along with the leftover (change) nanoErgs
+/// and tokens above the requested targets, or `InsufficientFunds` if
+/// `available` cannot cover the targets even when fully spent.
+pub fn select_boxes(
+    available: Vec<ErgoBox>,
+    target_nano_ergs: NanoErg,
+    target_tokens: Vec<Token>,
+) -> Result<(Vec<ErgoBox>, NanoErg, Vec<Token>)> {
+    let target_token_amounts: HashMap<TokenId, u64> = target_tokens
+        .iter()
+        .map(|t| (t.token_id.clone(), *t.amount.as_u64()))
+        .collect();
+
+    // Prefer boxes that hold at least one of the needed tokens first, so
+    // that we minimize the number of inputs required to satisfy the
+    // token targets.
+    let mut candidates = available;
+    candidates.sort_by_key(|b| {
+        let holds_needed_token = box_token_amounts(b)
+            .keys()
+            .any(|token_id| target_token_amounts.contains_key(token_id));
+        !holds_needed_token
+    });
+
+    let mut selected = vec![];
+    let mut collected_nano_ergs: NanoErg = 0;
+    let mut collected_tokens: HashMap<TokenId, u64> = HashMap::new();
+
+    for b in candidates {
+        let have_enough_nano_ergs = collected_nano_ergs >= target_nano_ergs;
+        let have_enough_tokens = target_token_amounts
+            .iter()
+            .all(|(id, amount)| collected_tokens.get(id).unwrap_or(&0) >= amount);
+        if have_enough_nano_ergs && have_enough_tokens {
+            break;
+        }
+
+        collected_nano_ergs += *b.value.as_u64();
+        for (token_id, amount) in box_token_amounts(&b) {
+            *collected_tokens.entry(token_id).or_insert(0) += amount;
+        }
+        selected.push(b);
+    }
+
+    if collected_nano_ergs < target_nano_ergs {
+        return Err(BoxVerificationError::InsufficientFunds(format!(
+            "Only found {} nanoErgs among the available boxes, but {} nanoErgs were required.",
+            collected_nano_ergs, target_nano_ergs
+        )));
+    }
+    for (token_id, amount) in &target_token_amounts {
+        let collected_amount = collected_tokens.get(token_id).unwrap_or(&0);
+        if collected_amount < amount {
+            return Err(BoxVerificationError::InsufficientFunds(format!(
+                "Only found {} of token {} among the available boxes, but {} were required.",
+                collected_amount, token_id, amount
+            )));
+        }
+    }
+
+    let change_nano_ergs = collected_nano_ergs - target_nano_ergs;
+    let mut change_tokens = vec![];
+    for (token_id, collected_amount) in collected_tokens {
+        let target_amount = target_token_amounts.get(&token_id).unwrap_or(&0);
+        let leftover = collected_amount - target_amount;
+        if leftover > 0 {
+            change_tokens.push(Token {
+                token_id,
+                amount: TokenAmount::try_from(leftover)
+                    .map_err(|e| BoxVerificationError::InvalidTokens(format!("{:?}", e)))?,
+            });
+        }
+    }
+
+    Ok((selected, change_nano_ergs, change_tokens))
+}
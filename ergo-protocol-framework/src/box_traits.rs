@@ -0,0 +1,167 @@
+// `BoxSpec`/`SpecifiedBox`/`WrapBox`: the declarative counterpart to
+// `Stage`, used by box wrapper types such as `MathBountyBox` (see
+// `#[derive(WrapBox)]` usages across the tutorials). Where `Stage` is
+// built around a single hand-written `verification_predicate` function,
+// a `BoxSpec` lets a protocol declare its address, nanoErg range, token
+// predicates, and register predicates directly as data, and a
+// `#[derive(WrapBox)]` wrapper gets `registers()`/`creation_height()`
+// accessors on the resulting box for free.
+
+use crate::stage::{
+    creation_height as box_creation_height, register_value, BoxVerificationError, Constant,
+    ErgoBox, NonMandatoryRegisterId, Result,
+};
+use ergo_lib::chain::address::{Address, AddressEncoder, NetworkPrefix};
+use ergo_lib::chain::token::{Token, TokenId};
+use ergo_lib::serialization::serializable::SigmaSerializable;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A predicate that the token matching a given `TokenId` held within an
+/// `ErgoBox` must pass.
+pub type TokenPredicate = fn(&Token) -> Result<()>;
+
+/// Either the exact `Constant` a register must decode to, or an
+/// arbitrary predicate it must pass.
+#[derive(Clone)]
+pub enum RegisterPredicate {
+    Matches(Constant),
+    Predicate(fn(&Constant) -> Result<()>),
+}
+
+impl RegisterPredicate {
+    fn verify(&self, constant: &Constant) -> Result<()> {
+        match self {
+            RegisterPredicate::Matches(expected) => {
+                if constant == expected {
+                    Ok(())
+                } else {
+                    Err(BoxVerificationError::InvalidRegisters(format!(
+                        "Register held `{:?}`, expected `{:?}`.",
+                        constant, expected
+                    )))
+                }
+            }
+            RegisterPredicate::Predicate(predicate) => predicate(constant),
+        }
+    }
+}
+
+/// A declarative specification of the shape an `ErgoBox` must have (its
+/// P2S address, nanoErg value range, token predicates, and register
+/// predicates) in order to be wrapped as a given `SpecifiedBox`.
+#[derive(Clone)]
+pub struct BoxSpec {
+    /// The address the box must be locked under, if any. It is compared
+    /// against the box's ErgoTree encoded as a mainnet P2S address, so a
+    /// P2PK address string does not match.
+    pub address: Option<String>,
+    /// The range of nanoErgs the box's value must fall within, if any.
+    pub value_range: Option<Range<u64>>,
+    /// Predicates that the token matching a given `TokenId` must pass.
+    pub token_predicates: Vec<(TokenId, TokenPredicate)>,
+    /// Predicates that the `Constant` held within a given register
+    /// (R4-R9) must pass.
+    pub register_predicates: Vec<(NonMandatoryRegisterId, RegisterPredicate)>,
+}
+
+impl BoxSpec {
+    pub fn new(
+        address: Option<String>,
+        value_range: Option<Range<u64>>,
+        token_predicates: Vec<(TokenId, TokenPredicate)>,
+        register_predicates: Vec<(NonMandatoryRegisterId, RegisterPredicate)>,
+    ) -> BoxSpec {
+        BoxSpec {
+            address,
+            value_range,
+            token_predicates,
+            register_predicates,
+        }
+    }
+
+    /// Verify that `ergo_box` satisfies every part of this `BoxSpec`:
+    /// its address, nanoErg value range, token predicates, and register
+    /// predicates.
+    pub fn verify_box(&self, ergo_box: &ErgoBox) -> Result<()> {
+        if let Some(address) = &self.address {
+            let encoder = AddressEncoder::new(NetworkPrefix::Mainnet);
+            let box_address =
+                encoder.address_to_str(&Address::P2S(ergo_box.ergo_tree.sigma_serialise_bytes()));
+            if address != &box_address {
+                return Err(BoxVerificationError::InvalidP2SAddress);
+            }
+        }
+
+        if let Some(value_range) = &self.value_range {
+            let value = *ergo_box.value.as_u64();
+            if !value_range.contains(&value) {
+                return Err(BoxVerificationError::InvalidErgsValue(format!(
+                    "Box holds {} nanoErgs, outside of the expected range {:?}.",
+                    value, value_range
+                )));
+            }
+        }
+
+        for (token_id, predicate) in &self.token_predicates {
+            let token = ergo_box
+                .tokens
+                .iter()
+                .find(|t| &t.token_id == token_id)
+                .ok_or_else(|| {
+                    BoxVerificationError::InvalidTokens(format!(
+                        "Box does not hold a token matching id {}.",
+                        token_id
+                    ))
+                })?;
+            predicate(token)?;
+        }
+
+        for (register_id, predicate) in &self.register_predicates {
+            let constant = register_value(ergo_box, *register_id)?;
+            predicate.verify(&constant)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Implemented by a box wrapper struct to pair it with the `BoxSpec` an
+/// `ErgoBox` must satisfy in order to be wrapped as that type.
+pub trait SpecifiedBox {
+    fn box_spec() -> BoxSpec;
+}
+
+/// Implemented (usually via `#[derive(WrapBox)]`) by any struct holding
+/// a verified `ErgoBox`, giving read access to the box itself plus
+/// convenience accessors for its registers and creation height.
+pub trait WrapBox {
+    fn ergo_box(&self) -> &ErgoBox;
+
+    /// Decode every non-mandatory register (R4-R9) the wrapped box
+    /// actually holds a `Constant` in.
+    fn registers(&self) -> HashMap<NonMandatoryRegisterId, Constant> {
+        [
+            NonMandatoryRegisterId::R4,
+            NonMandatoryRegisterId::R5,
+            NonMandatoryRegisterId::R6,
+            NonMandatoryRegisterId::R7,
+            NonMandatoryRegisterId::R8,
+            NonMandatoryRegisterId::R9,
+        ]
+        .iter()
+        .filter_map(|id| register_value(self.ergo_box(), *id).ok().map(|c| (*id, c)))
+        .collect()
+    }
+
+    /// Decode the `Constant` held in a given non-mandatory register
+    /// (R4-R9) of the wrapped box.
+    fn register(&self, register_id: NonMandatoryRegisterId) -> Result<Constant> {
+        register_value(self.ergo_box(), register_id)
+    }
+
+    /// The height at which the wrapped box was created.
+    fn creation_height(&self) -> Result<u64> {
+        box_creation_height(self.ergo_box())
+    }
+}
@@ -0,0 +1,26 @@
+// Data-input support for `BoxSpec`/`SpecifiedBox` wrappers.
+//
+// Many multi-stage protocols need to *read* an external, unspent box
+// (eg. an oracle pool box holding a price) without consuming it. This
+// module lets any verified box wrapper be converted into a `DataInput`,
+// which can then be passed as the `data_inputs` of
+// `balance_and_create_unsigned_tx` to be attached to a transaction
+// without being spent.
+
+use crate::box_traits::WrapBox;
+use ergo_lib::chain::transaction::DataInput;
+
+/// Allows a verified box wrapper (any `WrapBox`, eg. a `SpecifiedBox`
+/// generated via `#[derive(WrapBox)]`) to be referenced as a read-only
+/// `DataInput` on a transaction, without being spent.
+pub trait AsDataInput {
+    fn as_data_input(&self) -> DataInput;
+}
+
+impl<T: WrapBox> AsDataInput for T {
+    fn as_data_input(&self) -> DataInput {
+        DataInput {
+            box_id: self.ergo_box().box_id(),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// EIP-12 JSON encoding for `UnsignedTransaction`s produced by framework
+// Actions.
+//
+// Browser/mobile wallet dApp connectors (Nautilus, Yoroi, etc.) expect
+// the EIP-12 json representation of a transaction, where `BoxValue`s
+// and `TokenAmount`s are encoded as decimal strings rather than JSON
+// numbers, since nanoErg/token amounts routinely exceed the 53-bit
+// integer range that JS numbers can round-trip. This module mirrors
+// `UnsignedTransaction` with that encoding so the resulting JSON can be
+// handed directly to a connected wallet for signing.
+
+use ergo_lib::chain::ergo_box::{BoxId, ErgoBoxCandidate, NonMandatoryRegisterId};
+use ergo_lib::chain::token::{Token, TokenId};
+use ergo_lib::chain::transaction::unsigned::UnsignedTransaction;
+use ergo_lib::chain::transaction::DataInput;
+use ergo_lib::serialization::serializable::SigmaSerializable;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Eip12Error>;
+
+#[derive(Error, Debug)]
+pub enum Eip12Error {
+    #[error("Failed to serialize the `UnsignedTransaction` into EIP-12 JSON: {0}")]
+    SerializationFailed(String),
+}
+
+/// Serializes a nanoErg/token amount as a decimal string, per the EIP-12
+/// spec, rather than as a JSON number.
+fn amount_as_string<S>(value: &u64, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&value.to_string())
+}
+
+#[derive(Serialize)]
+struct Eip12Token {
+    #[serde(rename = "tokenId")]
+    token_id: TokenId,
+    #[serde(serialize_with = "amount_as_string")]
+    amount: u64,
+}
+
+impl From<&Token> for Eip12Token {
+    fn from(t: &Token) -> Eip12Token {
+        Eip12Token {
+            token_id: t.token_id.clone(),
+            amount: *t.amount.as_u64(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Eip12ErgoBoxCandidate {
+    #[serde(serialize_with = "amount_as_string")]
+    value: u64,
+    #[serde(rename = "ergoTree")]
+    ergo_tree: String,
+    assets: Vec<Eip12Token>,
+    #[serde(rename = "additionalRegisters")]
+    additional_registers: serde_json::Value,
+    #[serde(rename = "creationHeight")]
+    creation_height: u32,
+}
+
+/// Hex-encode every register the candidate actually holds (R4-R9),
+/// keyed by register name, as the EIP-12 spec requires. An empty box
+/// with no additional registers set still serializes to `{}`.
+fn additional_registers_as_json(c: &ErgoBoxCandidate) -> serde_json::Value {
+    let mut registers = serde_json::Map::new();
+    for (register_id, constant) in &c.additional_registers {
+        let register_name = match register_id {
+            NonMandatoryRegisterId::R4 => "R4",
+            NonMandatoryRegisterId::R5 => "R5",
+            NonMandatoryRegisterId::R6 => "R6",
+            NonMandatoryRegisterId::R7 => "R7",
+            NonMandatoryRegisterId::R8 => "R8",
+            NonMandatoryRegisterId::R9 => "R9",
+        };
+        let hex_encoded = base16::encode_lower(&constant.sigma_serialise_bytes());
+        registers.insert(register_name.to_string(), serde_json::Value::String(hex_encoded));
+    }
+    serde_json::Value::Object(registers)
+}
+
+impl From<&ErgoBoxCandidate> for Eip12ErgoBoxCandidate {
+    fn from(c: &ErgoBoxCandidate) -> Eip12ErgoBoxCandidate {
+        Eip12ErgoBoxCandidate {
+            value: *c.value.as_u64(),
+            ergo_tree: base16::encode_lower(&c.ergo_tree.sigma_serialise_bytes()),
+            assets: c.tokens.iter().map(Eip12Token::from).collect(),
+            additional_registers: additional_registers_as_json(c),
+            creation_height: c.creation_height,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Eip12UnsignedInput {
+    #[serde(rename = "boxId")]
+    box_id: BoxId,
+}
+
+#[derive(Serialize)]
+struct Eip12DataInput {
+    #[serde(rename = "boxId")]
+    box_id: BoxId,
+}
+
+#[derive(Serialize)]
+struct Eip12UnsignedTransaction {
+    inputs: Vec<Eip12UnsignedInput>,
+    #[serde(rename = "dataInputs")]
+    data_inputs: Vec<Eip12DataInput>,
+    outputs: Vec<Eip12ErgoBoxCandidate>,
+}
+
+/// Convert an `UnsignedTransaction` into its EIP-12 JSON representation,
+/// suitable for handing to a connected dApp wallet for signing.
+pub fn to_eip12_json(tx: &UnsignedTransaction) -> Result<String> {
+    let eip12_tx = Eip12UnsignedTransaction {
+        inputs: tx
+            .inputs()
+            .iter()
+            .map(|i| Eip12UnsignedInput {
+                box_id: i.box_id.clone(),
+            })
+            .collect(),
+        data_inputs: tx
+            .data_inputs()
+            .iter()
+            .map(|d: &DataInput| Eip12DataInput {
+                box_id: d.box_id.clone(),
+            })
+            .collect(),
+        outputs: tx
+            .output_candidates()
+            .iter()
+            .map(Eip12ErgoBoxCandidate::from)
+            .collect(),
+    };
+
+    serde_json::to_string(&eip12_tx).map_err(|e| Eip12Error::SerializationFailed(e.to_string()))
+}
+
+/// Allows an `UnsignedTransaction` produced by a protocol Action to be
+/// exported directly as EIP-12 JSON for a dApp connector to sign.
+pub trait ToEip12Json {
+    fn to_eip12_json(&self) -> Result<String>;
+}
+
+impl ToEip12Json for UnsignedTransaction {
+    fn to_eip12_json(&self) -> Result<String> {
+        to_eip12_json(self)
+    }
+}
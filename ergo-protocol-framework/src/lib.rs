@@ -0,0 +1,15 @@
+pub mod box_selection;
+pub mod box_traits;
+pub mod data_input;
+pub mod eip12;
+pub mod signing;
+pub mod stage;
+pub mod tx_creation;
+
+pub use box_selection::*;
+pub use box_traits::*;
+pub use data_input::*;
+pub use eip12::*;
+pub use signing::*;
+pub use stage::*;
+pub use tx_creation::*;
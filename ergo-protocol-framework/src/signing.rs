@@ -0,0 +1,51 @@
+// Local validation and signing for `UnsignedTransaction`s built by the
+// framework.
+//
+// Everything up to this point in the framework stops at producing an
+// `UnsignedTransaction`, leaving the caller to validate and sign it
+// through some other means. This module closes that gap: given an
+// `ErgoStateContext` (the pre-header plus recent block headers the
+// ErgoTree interpreter needs) and a prover holding the spending
+// secrets, it runs every input's script against the context, surfacing
+// any failure as a `TxSigningError` before the transaction is ever
+// broadcast, and otherwise returns the finished, signed `Transaction`.
+
+use crate::stage::ErgoBox;
+use ergo_lib::chain::ergo_state_context::ErgoStateContext;
+use ergo_lib::chain::transaction::unsigned::UnsignedTransaction;
+use ergo_lib::chain::transaction::Transaction;
+use ergo_lib::wallet::signing::TransactionContext;
+use ergo_lib::wallet::Wallet;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, TxSigningError>;
+
+#[derive(Error, Debug)]
+pub enum TxSigningError {
+    #[error("Failed to build the signing context for the transaction: {0}")]
+    InvalidTransactionContext(String),
+    #[error("The transaction failed script validation and could not be signed: {0}")]
+    SigningFailed(String),
+}
+
+/// Validate and sign an `UnsignedTransaction` produced by a protocol
+/// Action. `boxes_to_spend` and `data_boxes` must contain the actual
+/// `ErgoBox`es referenced by the transaction's inputs/data-inputs, and
+/// `state_context` must reflect the chain state the transaction will be
+/// submitted against. Running every input's guarding script against
+/// `state_context` surfaces a failing spending condition here instead of
+/// at broadcast time.
+pub fn sign_transaction(
+    wallet: &Wallet,
+    state_context: &ErgoStateContext,
+    unsigned_tx: UnsignedTransaction,
+    boxes_to_spend: Vec<ErgoBox>,
+    data_boxes: Vec<ErgoBox>,
+) -> Result<Transaction> {
+    let tx_context = TransactionContext::new(unsigned_tx, boxes_to_spend, data_boxes)
+        .map_err(|e| TxSigningError::InvalidTransactionContext(format!("{:?}", e)))?;
+
+    wallet
+        .sign_transaction(state_context, &tx_context)
+        .map_err(|e| TxSigningError::SigningFailed(format!("{:?}", e)))
+}
@@ -9,6 +9,7 @@ use crate::predicated_boxes::StageBox;
 pub use ergo_lib::ast::Constant;
 use ergo_lib::chain::address::{Address, AddressEncoder, NetworkPrefix};
 pub use ergo_lib::chain::ergo_box::ErgoBox;
+pub use ergo_lib::chain::ergo_box::NonMandatoryRegisterId;
 pub use ergo_lib::chain::token::TokenAmount;
 use ergo_lib::serialization::serializable::SigmaSerializable;
 use ergo_offchain_utilities::P2SAddressString;
@@ -27,6 +28,8 @@ pub enum BoxVerificationError {
     InvalidTokens(String),
     #[error("The provided `ErgoBox` did not pass the verification predicate because of a problem with the values within the registers of the box: {0}")]
     InvalidRegisters(String),
+    #[error("The available boxes do not hold enough nanoErgs/tokens to cover the requested target: {0}")]
+    InsufficientFunds(String),
     #[error("{0}")]
     OtherError(String),
 }
@@ -98,4 +101,35 @@ impl<ST: StageType> Stage<ST> {
 
         Ok(stage_box)
     }
+}
+
+/// Decode the `Constant` held within a given non-mandatory register
+/// (R4-R9) of an `ErgoBox`, failing with `InvalidRegisters` if the
+/// register is empty.
+pub fn register_value(b: &ErgoBox, register_id: NonMandatoryRegisterId) -> Result<Constant> {
+    b.get_register(register_id).ok_or_else(|| {
+        BoxVerificationError::InvalidRegisters(format!(
+            "Register {:?} is missing from the box.",
+            register_id
+        ))
+    })
+}
+
+/// The height at which a box was created, read directly off the box's
+/// `creation_height` field (not one of the additional R4-R9 registers).
+pub fn creation_height(b: &ErgoBox) -> Result<u64> {
+    Ok(b.creation_height as u64)
+}
+
+/// Verify that a box was created at least `min_age` blocks before
+/// `current_height`.
+pub fn verify_min_box_age(b: &ErgoBox, current_height: u64, min_age: u64) -> Result<()> {
+    let box_creation_height = creation_height(b)?;
+    if current_height < box_creation_height + min_age {
+        return Err(BoxVerificationError::InvalidRegisters(format!(
+            "Box was created at height {} and so is not yet {} blocks old at current height {}.",
+            box_creation_height, min_age, current_height
+        )));
+    }
+    Ok(())
 }
\ No newline at end of file
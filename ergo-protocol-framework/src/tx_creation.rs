@@ -0,0 +1,205 @@
+// Helpers for assembling a balanced `UnsignedTransaction` out of a set of
+// input boxes and a list of the "meaningful" output candidates an Action
+// actually cares about (eg. a protocol stage box, a payment box, etc).
+//
+// Rather than every Action hand-computing the leftover nanoErgs/tokens
+// that must flow back to the user, `balance_and_create_unsigned_tx` works
+// out the fee box and change box automatically and appends them, so an
+// Action author only has to specify the boxes that matter to their
+// protocol logic.
+
+use crate::stage::{BoxVerificationError, ErgoBox, Result};
+use ergo_lib::chain::ergo_box::ErgoBoxCandidate;
+use ergo_lib::chain::parameters::Parameters;
+use ergo_lib::chain::token::{Token, TokenAmount, TokenId};
+use ergo_lib::chain::transaction::unsigned::input::UnsignedInput;
+use ergo_lib::chain::transaction::unsigned::UnsignedTransaction;
+use ergo_lib::chain::transaction::DataInput;
+use ergo_lib::serialization::serializable::SigmaSerializable;
+use ergo_offchain_utilities::{ChangeBox, NanoErg, P2PKAddressString, TxFeeBox};
+use std::collections::HashMap;
+
+/// Sum up the nanoErgs held within a list of `ErgoBox`es.
+fn sum_nano_ergs(boxes: &[ErgoBox]) -> NanoErg {
+    boxes.iter().map(|b| *b.value.as_u64()).sum()
+}
+
+/// Sum up the nanoErgs that a list of `ErgoBoxCandidate`s will lock.
+fn sum_candidate_nano_ergs(candidates: &[ErgoBoxCandidate]) -> NanoErg {
+    candidates.iter().map(|c| *c.value.as_u64()).sum()
+}
+
+/// Tally up every distinct `TokenId` held across a list of `ErgoBox`es.
+fn tally_box_tokens(boxes: &[ErgoBox]) -> HashMap<TokenId, u64> {
+    let mut tally = HashMap::new();
+    for b in boxes {
+        for token in &b.tokens {
+            *tally.entry(token.token_id.clone()).or_insert(0) += *token.amount.as_u64();
+        }
+    }
+    tally
+}
+
+/// Tally up every distinct `TokenId` that a list of `ErgoBoxCandidate`s
+/// will lock.
+fn tally_candidate_tokens(candidates: &[ErgoBoxCandidate]) -> HashMap<TokenId, u64> {
+    let mut tally = HashMap::new();
+    for c in candidates {
+        for token in &c.tokens {
+            *tally.entry(token.token_id.clone()).or_insert(0) += *token.amount.as_u64();
+        }
+    }
+    tally
+}
+
+/// Given the `inputs` being spent and the "meaningful" `outputs` an
+/// Action wants to create, work out the leftover nanoErgs and tokens,
+/// append a `TxFeeBox` candidate and a change box candidate (guarded by
+/// `change_address`) for the remainder, and assemble the resulting
+/// `UnsignedTransaction`.
+///
+/// Fails with `InvalidErgsValue` if the inputs do not hold enough
+/// nanoErgs to cover the outputs plus the `transaction_fee`, and with
+/// `InvalidTokens` if the inputs do not hold enough of a token that the
+/// outputs require.
+pub fn balance_and_create_unsigned_tx(
+    inputs: Vec<ErgoBox>,
+    data_inputs: Vec<DataInput>,
+    outputs: Vec<ErgoBoxCandidate>,
+    change_address: P2PKAddressString,
+    transaction_fee: NanoErg,
+    current_height: u64,
+) -> Result<UnsignedTransaction> {
+    // Work out the leftover nanoErgs that must flow back as change
+    let total_input_nano_ergs = sum_nano_ergs(&inputs);
+    let total_output_nano_ergs = sum_candidate_nano_ergs(&outputs) + transaction_fee;
+    if total_output_nano_ergs > total_input_nano_ergs {
+        return Err(BoxVerificationError::InvalidErgsValue(format!(
+            "Inputs hold {} nanoErgs, which is not enough to cover {} nanoErgs of outputs and fees.",
+            total_input_nano_ergs, total_output_nano_ergs
+        )));
+    }
+    let total_change_nano_ergs = total_input_nano_ergs - total_output_nano_ergs;
+
+    // Work out the leftover tokens that must flow back as change. We
+    // validate against the union of every `TokenId` seen on either side
+    // so that a token appearing only in `outputs` (and not in `inputs`)
+    // is caught as `InvalidTokens` rather than silently minted.
+    let input_tokens = tally_box_tokens(&inputs);
+    let output_tokens = tally_candidate_tokens(&outputs);
+    let all_token_ids: std::collections::HashSet<&TokenId> =
+        input_tokens.keys().chain(output_tokens.keys()).collect();
+    let mut change_tokens = vec![];
+    for token_id in all_token_ids {
+        let input_amount = input_tokens.get(token_id).unwrap_or(&0);
+        let output_amount = output_tokens.get(token_id).unwrap_or(&0);
+        if output_amount > input_amount {
+            return Err(BoxVerificationError::InvalidTokens(format!(
+                "Inputs hold {} of token {}, which is not enough to cover {} required by outputs.",
+                input_amount, token_id, output_amount
+            )));
+        }
+        let leftover = input_amount - output_amount;
+        if leftover > 0 {
+            change_tokens.push(Token {
+                token_id: token_id.clone(),
+                amount: TokenAmount::try_from(leftover)
+                    .map_err(|e| BoxVerificationError::InvalidTokens(format!("{:?}", e)))?,
+            });
+        }
+    }
+
+    // Create the Transaction Fee box candidate
+    let transaction_fee_candidate = TxFeeBox::output_candidate(transaction_fee, current_height)
+        .map_err(|e| BoxVerificationError::OtherError(format!("{:?}", e)))?;
+
+    // Create the Change box candidate for the leftover nanoErgs/tokens
+    let change_box_candidate = ChangeBox::output_candidate(
+        &change_tokens,
+        total_change_nano_ergs,
+        &change_address,
+        current_height,
+    )
+    .map_err(|e| BoxVerificationError::OtherError(format!("{:?}", e)))?;
+
+    let mut all_outputs = outputs;
+    all_outputs.push(transaction_fee_candidate);
+    all_outputs.push(change_box_candidate);
+
+    let unsigned_inputs = inputs.iter().map(|b| UnsignedInput::from(b.box_id())).collect();
+
+    Ok(UnsignedTransaction::new(
+        unsigned_inputs,
+        data_inputs,
+        all_outputs,
+    ))
+}
+
+/// Like `balance_and_create_unsigned_tx`, but validates the outputs and
+/// fee against the live blockchain `Parameters` (eg. `min_value_per_byte`
+/// and the minimum transaction fee) instead of hardcoded constants, so
+/// that dust/underfunded boxes and an insufficient `transaction_fee` are
+/// caught here rather than on submission to the node.
+pub fn balance_and_create_unsigned_tx_with_params(
+    inputs: Vec<ErgoBox>,
+    data_inputs: Vec<DataInput>,
+    outputs: Vec<ErgoBoxCandidate>,
+    change_address: P2PKAddressString,
+    transaction_fee: NanoErg,
+    current_height: u64,
+    params: &Parameters,
+) -> Result<UnsignedTransaction> {
+    let unsigned_tx = balance_and_create_unsigned_tx(
+        inputs,
+        data_inputs,
+        outputs,
+        change_address,
+        transaction_fee,
+        current_height,
+    )?;
+
+    // Validate every output candidate that will actually end up on the
+    // transaction, including the `TxFeeBox`/change box candidates that
+    // `balance_and_create_unsigned_tx` appends above, so a small
+    // leftover change box can't slip through as dust unchecked.
+    for candidate in unsigned_tx.output_candidates() {
+        verify_meets_min_box_value(candidate, params)?;
+    }
+
+    verify_meets_min_fee(&unsigned_tx, transaction_fee, params)?;
+
+    Ok(unsigned_tx)
+}
+
+/// Verify that `transaction_fee` covers at least `min_fee_per_byte`
+/// nanoErgs per byte of the assembled transaction's serialized size, as
+/// demanded by `params`.
+fn verify_meets_min_fee(
+    unsigned_tx: &UnsignedTransaction,
+    transaction_fee: NanoErg,
+    params: &Parameters,
+) -> Result<()> {
+    let tx_size_bytes = unsigned_tx.sigma_serialise_bytes().len() as u64;
+    let min_fee = tx_size_bytes * *params.min_fee_per_byte() as u64;
+    if transaction_fee < min_fee {
+        return Err(BoxVerificationError::InvalidErgsValue(format!(
+            "Transaction fee of {} nanoErgs is below the minimum fee of {} nanoErgs required for a {} byte transaction under the current `Parameters`.",
+            transaction_fee, min_fee, tx_size_bytes
+        )));
+    }
+    Ok(())
+}
+
+/// Verify that an output candidate holds at least `min_value_per_byte`
+/// nanoErgs per byte of its serialized size, as demanded by `params`.
+fn verify_meets_min_box_value(candidate: &ErgoBoxCandidate, params: &Parameters) -> Result<()> {
+    let box_size_bytes = candidate.box_size_bytes();
+    let min_value = box_size_bytes as u64 * *params.min_value_per_byte() as u64;
+    if *candidate.value.as_u64() < min_value {
+        return Err(BoxVerificationError::InvalidErgsValue(format!(
+            "Output box holds {} nanoErgs, below the {} nanoErg minimum (dust limit) required for its {} byte size under the current `Parameters`.",
+            candidate.value.as_u64(), min_value, box_size_bytes
+        )));
+    }
+    Ok(())
+}
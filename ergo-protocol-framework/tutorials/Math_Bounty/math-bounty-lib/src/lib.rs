@@ -44,16 +44,16 @@ impl MathBountyProtocol {
         ergs_box_for_fee: ErgsBox,
         user_address: String,
     ) -> UnsignedTransaction {
-        let tx_inputs = vec![
-            ergs_box_for_bounty.as_unsigned_input(),
-            ergs_box_for_fee.as_unsigned_input(),
+        let inputs = vec![
+            ergs_box_for_bounty.ergo_box().clone(),
+            ergs_box_for_fee.ergo_box().clone(),
         ];
 
-        // Calculating left over change nanoErgs
-        let total_nano_ergs = ergs_box_for_bounty.nano_ergs() + ergs_box_for_fee.nano_ergs();
-        let total_change = total_nano_ergs - bounty_amount_in_nano_ergs - transaction_fee;
-
-        // Creating our Math Bounty Box output candidate
+        // Creating our Math Bounty Box output candidate. The fee box and
+        // change box are derived automatically by
+        // `balance_and_create_unsigned_tx` from the difference between
+        // `inputs` and this "meaningful" output, so we don't have to
+        // hand-compute the leftover change nanoErgs ourselves.
         let math_bounty_candidate = create_candidate(
             bounty_amount_in_nano_ergs,
             &"94hWSMqgxHtRNEWoKrJFGVNQEYX34zfX68FNxWr".to_string(),
@@ -63,23 +63,14 @@ impl MathBountyProtocol {
         )
         .unwrap();
 
-        // Create the Transaction Fee box candidate
-        let transaction_fee_candidate =
-            TxFeeBox::output_candidate(transaction_fee, current_height).unwrap();
-
-        // Create the Change box candidate
-        let change_box_candidate =
-            ChangeBox::output_candidate(&vec![], total_change, &user_address, current_height)
-                .unwrap();
-
-        // Our output candidates list, specifically with the Math Bounty box
-        // candidate being the first, meaning Output #0.
-        let output_candidates = vec![
-            math_bounty_candidate,
-            transaction_fee_candidate,
-            change_box_candidate,
-        ];
-
-        UnsignedTransaction::new(tx_inputs, vec![], output_candidates)
+        balance_and_create_unsigned_tx(
+            inputs,
+            vec![],
+            vec![math_bounty_candidate],
+            user_address,
+            transaction_fee,
+            current_height,
+        )
+        .unwrap()
     }
 }